@@ -0,0 +1,66 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(
+    name = "miniserve",
+    about = "A small, zero-configuration command-line HTTP server"
+)]
+pub struct CliArgs {
+    /// Path to a directory (or, for single-file mode, a single file) to serve
+    #[structopt(name = "PATH", parse(from_os_str))]
+    pub path: PathBuf,
+
+    /// The port to serve on
+    #[structopt(short = "p", long = "port", default_value = "8080")]
+    pub port: u16,
+
+    /// The interface(s) to bind to
+    #[structopt(short = "i", long = "interfaces")]
+    pub interfaces: Vec<IpAddr>,
+
+    /// Enable HTTP basic authentication, either "user:pass" or
+    /// "user:sha256:<hex digest>" / "user:sha512:<hex digest>"
+    #[structopt(short = "a", long = "auth")]
+    pub auth: Option<String>,
+
+    /// Enable file uploads
+    #[structopt(short = "u", long = "upload-files")]
+    pub file_upload: bool,
+
+    /// Mount the server under this URL path prefix, e.g. "files" makes
+    /// everything available under /files/...
+    #[structopt(long = "route-prefix")]
+    pub route_prefix: Option<String>,
+
+    /// Auto-expire uploaded files after this much time (e.g. "30s", "2h", "7d")
+    #[structopt(long = "upload-expire")]
+    pub upload_expire: Option<String>,
+
+    /// Store uploaded files under a randomly generated name instead of their
+    /// original filename
+    #[structopt(long = "random-names")]
+    pub random_names: bool,
+
+    /// Number of dictionary words to use when generating a random name
+    /// (only relevant together with `--random-names`)
+    #[structopt(long = "random-names-words", default_value = "3")]
+    pub random_names_words: usize,
+}
+
+impl CliArgs {
+    /// Normalize `--route-prefix` by trimming surrounding slashes and
+    /// percent-encoding it, so it can be safely spliced into a URL path.
+    /// Returns an empty string when no prefix was given.
+    pub fn route_prefix(&self) -> String {
+        match &self.route_prefix {
+            Some(prefix) => {
+                utf8_percent_encode(prefix.trim_matches('/'), NON_ALPHANUMERIC).to_string()
+            }
+            None => String::new(),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::errors::ContextualError;
+
+/// The hashing scheme used to store the password, if any.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AuthHash {
+    Plain,
+    Sha256,
+    Sha512,
+}
+
+/// Parsed representation of the `-a`/`--auth` argument.
+#[derive(Clone, Debug)]
+pub struct RequiredAuth {
+    pub username: String,
+    pub password: String,
+    pub hash: AuthHash,
+}
+
+/// Parse a CLI auth string of the form `username:password`,
+/// `username:sha256:<hex digest>` or `username:sha512:<hex digest>`.
+pub fn parse_auth(auth: &str) -> Result<RequiredAuth, ContextualError> {
+    let mut parts = auth.splitn(3, ':');
+    let username = parts
+        .next()
+        .ok_or_else(|| ContextualError::new("Invalid auth format, expected username:password"))?
+        .to_owned();
+
+    let rest: Vec<&str> = parts.collect();
+    let (hash, password) = match rest.as_slice() {
+        [password] => (AuthHash::Plain, (*password).to_owned()),
+        ["sha256", digest] => (AuthHash::Sha256, (*digest).to_owned()),
+        ["sha512", digest] => (AuthHash::Sha512, (*digest).to_owned()),
+        _ => {
+            return Err(ContextualError::new(format!(
+                "Couldn't parse auth parameter {}",
+                auth
+            )))
+        }
+    };
+
+    Ok(RequiredAuth {
+        username,
+        password,
+        hash,
+    })
+}
+
+/// Check a username/password pair against the required credentials.
+pub fn check_auth(required: &RequiredAuth, username: &str, password: &str) -> bool {
+    if username != required.username {
+        return false;
+    }
+
+    match required.hash {
+        AuthHash::Plain => password == required.password,
+        AuthHash::Sha256 => hex::encode(Sha256::digest(password.as_bytes())) == required.password,
+        AuthHash::Sha512 => hex::encode(Sha512::digest(password.as_bytes())) == required.password,
+    }
+}
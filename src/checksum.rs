@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+
+/// A file's checksum cache key: invalidated whenever the file's mtime or
+/// size changes, so edits don't keep serving a stale digest.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct CacheKey {
+    mtime: Option<SystemTime>,
+    size: u64,
+}
+
+/// Lazily computes and caches SHA-256 digests of served files, so repeated
+/// lookups by checksum don't re-hash the file every time.
+#[derive(Default)]
+pub struct ChecksumIndex {
+    cache: Mutex<HashMap<PathBuf, (CacheKey, String)>>,
+}
+
+impl ChecksumIndex {
+    pub fn new() -> Self {
+        ChecksumIndex {
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the hex-encoded SHA-256 digest of `path`, using the cached
+    /// value when the file hasn't changed since it was last computed.
+    fn digest_of(&self, path: &Path) -> io::Result<String> {
+        let metadata = fs::metadata(path)?;
+        let key = CacheKey {
+            mtime: metadata.modified().ok(),
+            size: metadata.len(),
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        if let Some((cached_key, digest)) = cache.get(path) {
+            if *cached_key == key {
+                return Ok(digest.clone());
+            }
+        }
+
+        let contents = fs::read(path)?;
+        let digest = hex::encode(Sha256::digest(&contents));
+        cache.insert(path.to_path_buf(), (key, digest.clone()));
+        Ok(digest)
+    }
+
+    /// Search `dir` (recursively) for a file whose SHA-256 digest matches
+    /// `hex_digest`, returning its path if found.
+    pub fn resolve(&self, dir: &Path, hex_digest: &str) -> Option<PathBuf> {
+        self.search_dir(dir, hex_digest)
+    }
+
+    fn search_dir(&self, dir: &Path, hex_digest: &str) -> Option<PathBuf> {
+        let entries = fs::read_dir(dir).ok()?;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                if let Some(found) = self.search_dir(&path, hex_digest) {
+                    return Some(found);
+                }
+            } else if let Ok(digest) = self.digest_of(&path) {
+                if digest == hex_digest {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Whether `s` looks like a SHA-256 digest: 64 lowercase hex characters.
+pub fn looks_like_sha256(s: &str) -> bool {
+    s.len() == 64 && s.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
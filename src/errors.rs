@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Any error that can occur while miniserve is running, together with some
+/// extra context to help the user fix it.
+#[derive(Debug)]
+pub struct ContextualError {
+    context: String,
+    source: Option<Box<dyn std::error::Error>>,
+}
+
+impl ContextualError {
+    pub fn new<S: Into<String>>(context: S) -> Self {
+        ContextualError {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    pub fn with_source<S: Into<String>, E: std::error::Error + 'static>(
+        context: S,
+        source: E,
+    ) -> Self {
+        ContextualError {
+            context: context.into(),
+            source: Some(Box::new(source)),
+        }
+    }
+}
+
+impl fmt::Display for ContextualError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{}: {}", self.context, source),
+            None => write!(f, "{}", self.context),
+        }
+    }
+}
+
+impl std::error::Error for ContextualError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_ref().map(|e| e.as_ref())
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::errors::ContextualError;
+
+/// How a tracked upload should expire.
+#[derive(Clone, Copy, Debug)]
+pub enum ExpiryPolicy {
+    /// The file is removed once `deadline` has passed.
+    Timed { deadline: Instant },
+    /// The file is removed right after it has been served once.
+    OneShot,
+}
+
+/// Tracks expiry policies for uploaded files, keyed by their stored filename.
+#[derive(Default)]
+pub struct ExpiringUploads {
+    entries: Mutex<HashMap<String, ExpiryPolicy>>,
+}
+
+impl ExpiringUploads {
+    pub fn new() -> Self {
+        ExpiringUploads {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn track(&self, filename: String, policy: ExpiryPolicy) {
+        self.entries.lock().unwrap().insert(filename, policy);
+    }
+
+    /// Checks whether `filename` may still be served right now.
+    ///
+    /// Once a timed entry has passed its deadline it is kept in the map
+    /// (not evicted) so that every subsequent check keeps reporting
+    /// `Expired`, rather than falling through to `Serve` once the entry is
+    /// gone.
+    pub fn check(&self, filename: &str) -> ExpiryCheck {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(filename).copied() {
+            Some(ExpiryPolicy::Timed { deadline }) => {
+                if Instant::now() >= deadline {
+                    ExpiryCheck::Expired
+                } else {
+                    ExpiryCheck::Serve
+                }
+            }
+            Some(ExpiryPolicy::OneShot) => {
+                entries.remove(filename);
+                ExpiryCheck::ServeThenDelete
+            }
+            None => ExpiryCheck::Serve,
+        }
+    }
+}
+
+/// Result of checking a tracked upload's expiry policy before serving it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExpiryCheck {
+    /// Serve the file normally.
+    Serve,
+    /// Serve the file, then unlink it from disk: it was a one-shot upload.
+    ServeThenDelete,
+    /// The file's deadline has passed; treat the request as a 404.
+    Expired,
+}
+
+/// Parse a human-friendly duration such as `30s`, `2h` or `7d` into a
+/// [`Duration`], by splitting off the trailing unit suffix.
+pub fn parse_duration(input: &str) -> Result<Duration, ContextualError> {
+    if input.is_empty() {
+        return Err(ContextualError::new("Duration cannot be empty"));
+    }
+
+    let (number, unit) = input.split_at(input.len() - 1);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| ContextualError::new(format!("Invalid duration: {}", input)))?;
+
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 60 * 60,
+        "d" => number * 60 * 60 * 24,
+        _ => {
+            return Err(ContextualError::new(format!(
+                "Unknown duration suffix in {}, expected one of s/m/h/d",
+                input
+            )))
+        }
+    };
+
+    Ok(Duration::from_secs(seconds))
+}
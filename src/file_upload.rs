@@ -0,0 +1,115 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_multipart::Multipart;
+use actix_web::{web, Error, HttpResponse};
+use futures::{StreamExt, TryStreamExt};
+
+use crate::errors::ContextualError;
+use crate::expiry::{ExpiringUploads, ExpiryPolicy};
+use crate::upload_names::UploadNames;
+
+/// Everything the upload handler needs beyond the multipart body itself.
+pub struct UploadOptions {
+    pub upload_dir: PathBuf,
+    pub upload_expire: Option<Duration>,
+    pub expiring_uploads: Arc<ExpiringUploads>,
+    /// When set, store uploads under a random slug rather than their
+    /// original filename (see `--random-names`).
+    pub random_names: bool,
+    pub random_names_words: usize,
+    pub upload_names: Arc<UploadNames>,
+}
+
+/// Handle a single `multipart/form-data` upload into `options.upload_dir`,
+/// writing the uploaded file(s) under their original filename (or, with
+/// `--random-names`, a generated slug) and registering an expiry policy
+/// when requested. Responds with `/` followed by the stored filename; any
+/// `--route-prefix` is not included in that path.
+pub async fn upload_file(
+    options: web::Data<UploadOptions>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, Error> {
+    let mut stored_filename = None;
+    let mut one_shot = false;
+
+    while let Ok(Some(mut field)) = payload.try_next().await {
+        let content_disposition = field.content_disposition();
+        let field_name = content_disposition
+            .as_ref()
+            .and_then(|cd| cd.get_name())
+            .unwrap_or("")
+            .to_owned();
+
+        if field_name == "one_shot" {
+            let mut value = Vec::new();
+            while let Some(chunk) = field.next().await {
+                value.extend_from_slice(&chunk?);
+            }
+            one_shot = matches!(String::from_utf8_lossy(&value).as_ref(), "on" | "true");
+            continue;
+        }
+
+        let original_filename = content_disposition
+            .and_then(|cd| cd.get_filename().map(String::from))
+            .ok_or_else(|| {
+                actix_web::error::ErrorBadRequest(ContextualError::new(
+                    "Missing filename in upload",
+                ))
+            })?;
+
+        let stored_name = if options.random_names {
+            options.upload_names.reserve_unique_slug(
+                &options.upload_dir,
+                options.random_names_words,
+                &original_filename,
+            )
+        } else {
+            original_filename
+        };
+
+        let dest_path = sanitize_dest(&options.upload_dir, &stored_name)?;
+        let mut f = fs::File::create(&dest_path)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+        while let Some(chunk) = field.next().await {
+            let data = chunk?;
+            f.write_all(&data)
+                .map_err(actix_web::error::ErrorInternalServerError)?;
+        }
+
+        stored_filename = Some(stored_name);
+    }
+
+    let stored_filename = stored_filename.ok_or_else(|| {
+        actix_web::error::ErrorBadRequest(ContextualError::new("No file was uploaded"))
+    })?;
+
+    if one_shot {
+        options
+            .expiring_uploads
+            .track(stored_filename.clone(), ExpiryPolicy::OneShot);
+    } else if let Some(expire_after) = options.upload_expire {
+        options.expiring_uploads.track(
+            stored_filename.clone(),
+            ExpiryPolicy::Timed {
+                deadline: Instant::now() + expire_after,
+            },
+        );
+    }
+
+    Ok(HttpResponse::Ok().body(format!("/{}", stored_filename)))
+}
+
+/// Make sure the uploaded file ends up inside `upload_dir` by keeping only
+/// the last component of `filename`; a name without one (e.g. `..`) is
+/// rejected.
+fn sanitize_dest(upload_dir: &Path, filename: &str) -> Result<PathBuf, Error> {
+    let name = Path::new(filename)
+        .file_name()
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("Invalid filename"))?;
+    Ok(upload_dir.join(name))
+}
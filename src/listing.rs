@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+
+use crate::errors::ContextualError;
+
+/// A single entry in a directory listing.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    /// The actual filename on disk, used to build the link target.
+    pub name: String,
+    pub is_dir: bool,
+    /// A friendlier name to display instead of `name`, e.g. the original
+    /// filename behind a `--random-names` upload slug.
+    pub display_name: Option<String>,
+}
+
+impl Entry {
+    pub fn new(name: String, is_dir: bool) -> Self {
+        Entry {
+            name,
+            is_dir,
+            display_name: None,
+        }
+    }
+}
+
+/// List all entries of `dir` (hidden ones included), directories first,
+/// then files, both alphabetically sorted.
+pub fn list_dir(dir: &Path) -> Result<Vec<Entry>, ContextualError> {
+    let mut entries = vec![];
+
+    for entry in fs::read_dir(dir)
+        .map_err(|e| ContextualError::with_source("Failed to read directory", e))?
+    {
+        let entry = entry.map_err(|e| ContextualError::with_source("Failed to read entry", e))?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let file_type = entry
+            .file_type()
+            .map_err(|e| ContextualError::with_source("Failed to read file type", e))?;
+
+        entries.push(Entry::new(file_name, file_type.is_dir()));
+    }
+
+    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    });
+
+    Ok(entries)
+}
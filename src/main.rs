@@ -0,0 +1,322 @@
+mod args;
+mod auth;
+mod checksum;
+mod errors;
+mod expiry;
+mod file_upload;
+mod listing;
+mod random_name;
+mod renderer;
+mod upload_names;
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use actix_web::dev::ServiceRequest;
+use actix_web::http::StatusCode;
+use actix_web::middleware::Condition;
+use actix_web::{web, App, Error, HttpRequest, HttpResponse, HttpServer};
+use actix_web_httpauth::extractors::basic::BasicAuth;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::Deserialize;
+use structopt::StructOpt;
+
+use crate::args::CliArgs;
+use crate::auth::RequiredAuth;
+use crate::errors::ContextualError;
+use crate::checksum::ChecksumIndex;
+use crate::expiry::{ExpiringUploads, ExpiryCheck};
+use crate::upload_names::UploadNames;
+
+/// State shared across all request handlers.
+pub struct MiniserveConfig {
+    /// The path given on the command line. If `path_is_file` is set, this
+    /// points at a single file to be served on every request; otherwise it
+    /// is the root directory to serve/list.
+    pub path: PathBuf,
+
+    /// Whether `path` is a single regular file rather than a directory.
+    pub path_is_file: bool,
+
+    /// Whether file uploads are enabled.
+    pub file_upload: bool,
+
+    /// Normalized (slash-free, percent-encoded) `--route-prefix` value, or
+    /// an empty string when the server isn't mounted under a prefix.
+    pub route_prefix: String,
+
+    /// Expiry policies for uploaded files, shared with the upload handler.
+    pub expiring_uploads: Arc<ExpiringUploads>,
+
+    /// Slug -> original filename mapping for `--random-names` uploads.
+    pub upload_names: Arc<UploadNames>,
+
+    /// Cache of per-file SHA-256 digests, for content-addressed lookups.
+    pub checksums: Arc<ChecksumIndex>,
+}
+
+/// Build a request-validation callback for [`HttpAuthentication::basic`],
+/// capturing `required_auth` directly rather than looking it up via
+/// app_data, which middleware added through `wrap()` can't see.
+fn make_auth_validator(
+    required_auth: Arc<RequiredAuth>,
+) -> impl Fn(ServiceRequest, BasicAuth) -> futures::future::Ready<Result<ServiceRequest, Error>> {
+    move |req, credentials| {
+        let password = credentials.password().map(|p| p.as_ref()).unwrap_or("");
+        let result = if auth::check_auth(&required_auth, credentials.user_id(), password) {
+            Ok(req)
+        } else {
+            Err(actix_web::error::ErrorUnauthorized("Invalid credentials"))
+        };
+        futures::future::ready(result)
+    }
+}
+
+/// Query parameters accepted by the file-serving handler.
+#[derive(Debug, Deserialize)]
+pub struct ServeQuery {
+    /// When set, force the response to be downloaded (`Content-Disposition:
+    /// attachment`) rather than rendered inline by the browser.
+    #[serde(default)]
+    pub download: bool,
+}
+
+async fn index(
+    req: HttpRequest,
+    query: web::Query<ServeQuery>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let config = req
+        .app_data::<web::Data<MiniserveConfig>>()
+        .expect("MiniserveConfig missing");
+
+    if config.path_is_file {
+        return serve_file(&config.path, query.download);
+    }
+
+    let filename = req.match_info().query("filename");
+
+    if checksum::looks_like_sha256(filename) {
+        if let Some(path) = config.checksums.resolve(&config.path, filename) {
+            let name = path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default();
+            return serve_tracked_file(&path, &name, &config.expiring_uploads, query.download);
+        }
+        // No file has this checksum; fall through in case `filename` is
+        // itself the literal name of a file, rather than 404ing outright.
+    }
+
+    let target = resolve_within(&config.path, filename)?;
+
+    let metadata = std::fs::metadata(&target)
+        .map_err(|_| actix_web::error::ErrorNotFound("File not found"))?;
+
+    if metadata.is_file() {
+        return serve_tracked_file(&target, filename, &config.expiring_uploads, query.download);
+    }
+
+    let mut entries = listing::list_dir(&target)
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    for entry in &mut entries {
+        entry.display_name = config.upload_names.display_name(&entry.name);
+    }
+
+    let upload_route = if config.file_upload {
+        Some("/upload")
+    } else {
+        None
+    };
+
+    let body = renderer::page(&entries, upload_route, &config.route_prefix, filename);
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(body))
+}
+
+/// Resolve `filename` against `root`, rejecting any path that escapes it
+/// (e.g. via `..` components or symlinks) before it ever reaches `fs::read`.
+fn resolve_within(root: &Path, filename: &str) -> Result<PathBuf, actix_web::Error> {
+    let target = if filename.is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(filename)
+    };
+
+    let canonical_root = root
+        .canonicalize()
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let canonical_target = target
+        .canonicalize()
+        .map_err(|_| actix_web::error::ErrorNotFound("File not found"))?;
+
+    if !canonical_target.starts_with(&canonical_root) {
+        return Err(actix_web::error::ErrorNotFound("File not found"));
+    }
+
+    Ok(canonical_target)
+}
+
+/// Serve `path` under `name`'s tracked expiry policy: refuse (and clean up
+/// on disk) once expired, serve normally otherwise, and unlink one-shot
+/// uploads right after they've been served.
+fn serve_tracked_file(
+    path: &Path,
+    name: &str,
+    expiring_uploads: &ExpiringUploads,
+    force_download: bool,
+) -> Result<HttpResponse, actix_web::Error> {
+    match expiring_uploads.check(name) {
+        ExpiryCheck::Expired => {
+            let _ = std::fs::remove_file(path);
+            Err(actix_web::error::ErrorNotFound("File has expired"))
+        }
+        ExpiryCheck::Serve => serve_file(path, force_download),
+        ExpiryCheck::ServeThenDelete => {
+            let response = serve_file(path, force_download)?;
+            let _ = std::fs::remove_file(path);
+            Ok(response)
+        }
+    }
+}
+
+/// Serve `path`'s contents. When `force_download` is set, override the
+/// `Content-Type` to `application/octet-stream` and ask the browser to save
+/// the file instead of rendering it inline.
+fn serve_file(path: &Path, force_download: bool) -> Result<HttpResponse, actix_web::Error> {
+    let contents =
+        std::fs::read(path).map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if force_download {
+        let filename = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        return Ok(HttpResponse::build(StatusCode::OK)
+            .content_type("application/octet-stream")
+            .header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", filename),
+            )
+            .body(contents));
+    }
+
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    Ok(HttpResponse::build(StatusCode::OK)
+        .content_type(mime.as_ref())
+        .body(contents))
+}
+
+fn run(args: CliArgs) -> Result<(), ContextualError> {
+    let metadata = std::fs::metadata(&args.path)
+        .map_err(|e| ContextualError::with_source("Couldn't stat served path", e))?;
+    let path_is_file = metadata.is_file();
+
+    let required_auth = args
+        .auth
+        .as_ref()
+        .map(|a| auth::parse_auth(a))
+        .transpose()?
+        .map(Arc::new);
+
+    let upload_dir = args.path.clone();
+    let file_upload = args.file_upload;
+    let port = args.port;
+    let path = args.path.clone();
+    let route_prefix = args.route_prefix();
+    let scope_path = if route_prefix.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", route_prefix)
+    };
+    let upload_expire = args
+        .upload_expire
+        .as_ref()
+        .map(|d| expiry::parse_duration(d))
+        .transpose()?;
+    let expiring_uploads = Arc::new(ExpiringUploads::new());
+    let upload_names = Arc::new(UploadNames::new());
+    let checksums = Arc::new(ChecksumIndex::new());
+    let random_names = args.random_names;
+    let random_names_words = args.random_names_words;
+    let interfaces = if args.interfaces.is_empty() {
+        vec![IpAddr::V4(Ipv4Addr::UNSPECIFIED)]
+    } else {
+        args.interfaces.clone()
+    };
+
+    actix_rt::System::new("miniserve").block_on(async move {
+        let mut server = HttpServer::new(move || {
+            let config = MiniserveConfig {
+                path: path.clone(),
+                path_is_file,
+                file_upload,
+                route_prefix: route_prefix.clone(),
+                expiring_uploads: expiring_uploads.clone(),
+                upload_names: upload_names.clone(),
+                checksums: checksums.clone(),
+            };
+
+            let mut scope = web::scope(&scope_path)
+                .data(config)
+                .route("/", web::get().to(index))
+                .route("/{filename:.*}", web::get().to(index));
+
+            if file_upload && !path_is_file {
+                let upload_options = web::Data::new(file_upload::UploadOptions {
+                    upload_dir: upload_dir.clone(),
+                    upload_expire,
+                    expiring_uploads: expiring_uploads.clone(),
+                    random_names,
+                    random_names_words,
+                    upload_names: upload_names.clone(),
+                });
+                scope = scope.route(
+                    "/upload",
+                    web::post().to(move |payload| {
+                        file_upload::upload_file(upload_options.clone(), payload)
+                    }),
+                );
+            }
+
+            let auth_enabled = required_auth.is_some();
+            let validator_auth = required_auth.clone().unwrap_or_else(|| {
+                Arc::new(RequiredAuth {
+                    username: String::new(),
+                    password: String::new(),
+                    hash: auth::AuthHash::Plain,
+                })
+            });
+
+            App::new()
+                .wrap(Condition::new(
+                    auth_enabled,
+                    HttpAuthentication::basic(make_auth_validator(validator_auth)),
+                ))
+                .service(scope)
+        });
+
+        for interface in &interfaces {
+            server = server
+                .bind((*interface, port))
+                .map_err(|e| ContextualError::with_source("Failed to bind to the given port", e))?;
+        }
+
+        server
+            .run()
+            .await
+            .map_err(|e| ContextualError::with_source("Server error", e))
+    })
+}
+
+fn main() {
+    let args = CliArgs::from_args();
+
+    if let Err(e) = run(args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,19 @@
+use rand::seq::SliceRandom;
+
+/// A small built-in word list, good enough to produce memorable,
+/// unguessable-enough slugs without shipping a huge dictionary.
+const WORDS: &[&str] = &[
+    "anchor", "bramble", "canyon", "delta", "ember", "falcon", "glacier", "harbor", "indigo",
+    "jasper", "kettle", "lumen", "meadow", "nimbus", "onyx", "piston", "quartz", "ridge", "sable",
+    "thicket", "umber", "violet", "willow", "xenon", "yonder", "zephyr",
+];
+
+/// Generate a slug made of `word_count` (at least one) dictionary words
+/// joined by hyphens, e.g. `glacier-ember-onyx`.
+pub fn word_slug(word_count: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..word_count.max(1))
+        .map(|_| *WORDS.choose(&mut rng).expect("word list is non-empty"))
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,56 @@
+use v_htmlescape::escape;
+
+use crate::listing::Entry;
+
+/// Render the full HTML page for a directory listing.
+///
+/// `base` is the (already percent-encoded, slash-free) `--route-prefix`
+/// value, or an empty string when the server isn't mounted under a prefix.
+/// `current_dir` is the listed directory's path relative to the served
+/// root (empty string for the root itself), so links to entries inside a
+/// subdirectory point at that subdirectory rather than back at the root.
+pub fn page(entries: &[Entry], upload_route: Option<&str>, base: &str, current_dir: &str) -> String {
+    let root = if base.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", base)
+    };
+
+    let mut rows = String::new();
+    for entry in entries {
+        let href = if current_dir.is_empty() {
+            format!("{}/{}", root, escape(&entry.name))
+        } else {
+            format!("{}/{}/{}", root, current_dir, escape(&entry.name))
+        };
+        let label = escape(entry.display_name.as_deref().unwrap_or(&entry.name)).to_string();
+        rows.push_str(&format!("<li><a href=\"{}\">{}</a></li>\n", href, label));
+    }
+
+    let upload_form = match upload_route {
+        Some(action) => format!(
+            r#"<form id="file_submit" action="{}{}" method="POST" enctype="multipart/form-data">
+  <input type="file" name="file_to_upload" />
+  <label><input type="checkbox" name="one_shot" /> Delete after first download</label>
+  <input type="submit" value="Upload" />
+</form>"#,
+            root,
+            escape(action)
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>miniserve</title></head>
+<body>
+<ul>
+{rows}</ul>
+{upload_form}
+</body>
+</html>"#,
+        rows = rows,
+        upload_form = upload_form,
+    )
+}
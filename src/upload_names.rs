@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::random_name;
+
+/// Tracks the mapping from a randomly generated upload slug back to the
+/// original filename the uploader used, so the directory listing can still
+/// show a friendly name while links use the unguessable slug.
+#[derive(Default)]
+pub struct UploadNames {
+    display_names: Mutex<HashMap<String, String>>,
+}
+
+impl UploadNames {
+    pub fn new() -> Self {
+        UploadNames {
+            display_names: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Generate a slug (made of `word_count` dictionary words) that collides
+    /// neither with an already-reserved slug nor with an existing file in
+    /// `upload_dir`, then reserve it for `original_filename`.
+    pub fn reserve_unique_slug(
+        &self,
+        upload_dir: &Path,
+        word_count: usize,
+        original_filename: &str,
+    ) -> String {
+        let mut display_names = self.display_names.lock().unwrap();
+
+        loop {
+            let slug = random_name::word_slug(word_count);
+            if display_names.contains_key(&slug) || upload_dir.join(&slug).exists() {
+                continue;
+            }
+
+            display_names.insert(slug.clone(), original_filename.to_owned());
+            return slug;
+        }
+    }
+
+    /// The friendly name that was originally uploaded under `slug`, if any.
+    pub fn display_name(&self, slug: &str) -> Option<String> {
+        self.display_names.lock().unwrap().get(slug).cloned()
+    }
+}
@@ -3,8 +3,8 @@ use assert_fs::fixture::TempDir;
 use assert_fs::prelude::*;
 use clap::{crate_name, crate_version};
 use port_check::free_local_port;
-use reqwest;
 use reqwest::multipart;
+use rstest::fixture;
 use rstest::rstest;
 use select::document::Document;
 use select::predicate::{Attr, Text};
@@ -12,12 +12,14 @@ use std::process::{Command, Stdio};
 use std::thread::sleep;
 use std::time::Duration;
 use rstest::rstest_parametrize;
+use sha2::{Digest, Sha256};
 
-type Error = Box<std::error::Error>;
+type Error = Box<dyn std::error::Error>;
 
 static FILES: &[&str] = &["test.txt", "test.html", "test.mkv"];
 
 /// Test fixture which creates a temporary directory with a few files inside.
+#[fixture]
 pub fn tmpdir() -> TempDir {
     let tmpdir = assert_fs::TempDir::new().expect("Couldn't create a temp dir for tests");
     for &file in FILES {
@@ -30,6 +32,7 @@ pub fn tmpdir() -> TempDir {
 }
 
 /// Get a free port.
+#[fixture]
 pub fn port() -> u16 {
     free_local_port().expect("Couldn't find a free local port")
 }
@@ -54,6 +57,28 @@ fn serves_requests_with_no_options(tmpdir: TempDir) -> Result<(), Error> {
     Ok(())
 }
 
+#[rstest]
+fn serves_single_file(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let file_path = tmpdir.path().join("test.txt");
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(&file_path)
+        .arg("-p")
+        .arg(port.to_string())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let mut body =
+        reqwest::get(format!("http://localhost:{}", port).as_str())?.error_for_status()?;
+    assert_eq!(body.text()?, "Test Hello Yes");
+
+    child.kill()?;
+
+    Ok(())
+}
+
 #[rstest]
 fn serves_requests_with_non_default_port(tmpdir: TempDir, port: u16) -> Result<(), Error> {
     let mut child = Command::cargo_bin("miniserve")?
@@ -170,6 +195,434 @@ fn uploading_files_works(tmpdir: TempDir, port: u16) -> Result<(), Error> {
     Ok(())
 }
 
+#[rstest]
+fn uploaded_files_expire(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let test_file_name = "uploaded test file.txt";
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .arg("-u")
+        .arg("--upload-expire")
+        .arg("1s")
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let body = reqwest::get(format!("http://localhost:{}", port).as_str())?.error_for_status()?;
+    let parsed = Document::from_read(body)?;
+    let upload_action = parsed
+        .find(Attr("id", "file_submit"))
+        .next()
+        .expect("Couldn't find element with id=file_submit")
+        .attr("action")
+        .expect("Upload form doesn't have action attribute")
+        .to_owned();
+
+    let form = multipart::Form::new();
+    let part = multipart::Part::text("this should expire")
+        .file_name(test_file_name)
+        .mime_str("text/plain")?;
+    let form = form.part("file_to_upload", part);
+
+    let client = reqwest::Client::new();
+    client
+        .post(format!("http://localhost:{}{}", port, upload_action).as_str())
+        .multipart(form)
+        .send()?
+        .error_for_status()?;
+
+    let file_url = format!(
+        "http://localhost:{}/{}",
+        port,
+        test_file_name.replace(" ", "%20")
+    );
+
+    reqwest::get(file_url.as_str())?.error_for_status()?;
+
+    sleep(Duration::from_secs(2));
+
+    let status = reqwest::get(file_url.as_str())?.status();
+    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+
+    // An expired file must stay gone, not just 404 once.
+    let status = reqwest::get(file_url.as_str())?.status();
+    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn one_shot_uploads_are_deleted_after_first_download(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let test_file_name = "uploaded test file.txt";
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .arg("-u")
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let body = reqwest::get(format!("http://localhost:{}", port).as_str())?.error_for_status()?;
+    let parsed = Document::from_read(body)?;
+    let upload_action = parsed
+        .find(Attr("id", "file_submit"))
+        .next()
+        .expect("Couldn't find element with id=file_submit")
+        .attr("action")
+        .expect("Upload form doesn't have action attribute")
+        .to_owned();
+
+    let form = multipart::Form::new();
+    let part = multipart::Part::text("this should be downloaded once")
+        .file_name(test_file_name)
+        .mime_str("text/plain")?;
+    let form = form.part("file_to_upload", part);
+    let form = form.text("one_shot", "on");
+
+    let client = reqwest::Client::new();
+    client
+        .post(format!("http://localhost:{}{}", port, upload_action).as_str())
+        .multipart(form)
+        .send()?
+        .error_for_status()?;
+
+    let file_url = format!(
+        "http://localhost:{}/{}",
+        port,
+        test_file_name.replace(" ", "%20")
+    );
+
+    reqwest::get(file_url.as_str())?.error_for_status()?;
+
+    let status = reqwest::get(file_url.as_str())?.status();
+    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn random_names_obscures_uploaded_filename(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let test_file_name = "test file.txt";
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .arg("-u")
+        .arg("--random-names")
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let body = reqwest::get(format!("http://localhost:{}", port).as_str())?.error_for_status()?;
+    let parsed = Document::from_read(body)?;
+    let upload_action = parsed
+        .find(Attr("id", "file_submit"))
+        .next()
+        .expect("Couldn't find element with id=file_submit")
+        .attr("action")
+        .expect("Upload form doesn't have action attribute")
+        .to_owned();
+
+    let form = multipart::Form::new();
+    let part = multipart::Part::text("this should be reachable under a random name")
+        .file_name(test_file_name)
+        .mime_str("text/plain")?;
+    let form = form.part("file_to_upload", part);
+
+    let client = reqwest::Client::new();
+    let mut response = client
+        .post(format!("http://localhost:{}{}", port, upload_action).as_str())
+        .multipart(form)
+        .send()?
+        .error_for_status()?;
+
+    let returned_url = response.text()?;
+    assert!(!returned_url.contains(test_file_name));
+
+    let mut body = reqwest::get(format!("http://localhost:{}{}", port, returned_url).as_str())?
+        .error_for_status()?;
+    assert_eq!(
+        body.text()?,
+        "this should be reachable under a random name"
+    );
+
+    // The listing should still show the original filename to the user.
+    let body = reqwest::get(format!("http://localhost:{}", port).as_str())?.error_for_status()?;
+    let parsed = Document::from_read(body)?;
+    assert!(parsed.find(Text).any(|x| x.text() == test_file_name));
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest_parametrize(
+    route_prefix,
+    case("files"),
+    case("/files/"),
+)]
+fn serves_requests_with_route_prefix(route_prefix: &str) -> Result<(), Error> {
+    let tmpdir = self::tmpdir();
+    let port = self::port();
+    let test_file_name = "uploaded test file.txt";
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .arg("-u")
+        .arg("--route-prefix")
+        .arg(route_prefix)
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let body =
+        reqwest::get(format!("http://localhost:{}/files/", port).as_str())?.error_for_status()?;
+    let parsed = Document::from_read(body)?;
+    for &file in FILES {
+        assert!(parsed.find(Text).any(|x| x.text() == file));
+    }
+
+    // The upload form's action must also live under the prefix.
+    let upload_action = parsed
+        .find(Attr("id", "file_submit"))
+        .next()
+        .expect("Couldn't find element with id=file_submit")
+        .attr("action")
+        .expect("Upload form doesn't have action attribute");
+    assert!(upload_action.starts_with("/files/"));
+
+    let form = multipart::Form::new();
+    let part = multipart::Part::text("this should be uploaded")
+        .file_name(test_file_name)
+        .mime_str("text/plain")?;
+    let form = form.part("file_to_upload", part);
+
+    let client = reqwest::Client::new();
+    client
+        .post(format!("http://localhost:{}{}", port, upload_action).as_str())
+        .multipart(form)
+        .send()?
+        .error_for_status()?;
+
+    let body = reqwest::get(format!("http://localhost:{}/files/", port).as_str())?;
+    let parsed = Document::from_read(body)?;
+    assert!(parsed.find(Text).any(|x| x.text() == test_file_name));
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn download_query_param_forces_attachment(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let response = reqwest::get(format!("http://localhost:{}/test.html?download=true", port).as_str())?
+        .error_for_status()?;
+
+    assert_eq!(
+        response
+            .headers()
+            .get("Content-Disposition")
+            .expect("Missing Content-Disposition header"),
+        "attachment; filename=\"test.html\""
+    );
+    assert_eq!(
+        response
+            .headers()
+            .get("Content-Type")
+            .expect("Missing Content-Type header"),
+        "application/octet-stream"
+    );
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn serves_files_by_checksum(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let contents = std::fs::read(tmpdir.path().join("test.txt"))?;
+    let digest = hex::encode(Sha256::digest(&contents));
+
+    let mut body =
+        reqwest::get(format!("http://localhost:{}/{}", port, digest).as_str())?.error_for_status()?;
+    assert_eq!(body.text()?, "Test Hello Yes");
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn hex_named_file_is_still_servable_by_name(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let hex_name = "a".repeat(64);
+    std::fs::write(tmpdir.path().join(&hex_name), "actual file contents")?;
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let mut body =
+        reqwest::get(format!("http://localhost:{}/{}", port, hex_name).as_str())?.error_for_status()?;
+    assert_eq!(body.text()?, "actual file contents");
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn checksum_lookup_honors_expiry(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let test_file_name = "expiring.txt";
+    let contents = "this should expire";
+    let digest = hex::encode(Sha256::digest(contents.as_bytes()));
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .arg("-u")
+        .arg("--upload-expire")
+        .arg("1s")
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let body = reqwest::get(format!("http://localhost:{}", port).as_str())?.error_for_status()?;
+    let parsed = Document::from_read(body)?;
+    let upload_action = parsed
+        .find(Attr("id", "file_submit"))
+        .next()
+        .expect("Couldn't find element with id=file_submit")
+        .attr("action")
+        .expect("Upload form doesn't have action attribute")
+        .to_owned();
+
+    let form = multipart::Form::new();
+    let part = multipart::Part::text(contents)
+        .file_name(test_file_name)
+        .mime_str("text/plain")?;
+    let form = form.part("file_to_upload", part);
+
+    let client = reqwest::Client::new();
+    client
+        .post(format!("http://localhost:{}{}", port, upload_action).as_str())
+        .multipart(form)
+        .send()?
+        .error_for_status()?;
+
+    let checksum_url = format!("http://localhost:{}/{}", port, digest);
+    reqwest::get(checksum_url.as_str())?.error_for_status()?;
+
+    sleep(Duration::from_secs(2));
+
+    let status = reqwest::get(checksum_url.as_str())?.status();
+    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn rejects_path_traversal(tmpdir: TempDir, port: u16) -> Result<(), Error> {
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let status =
+        reqwest::get(format!("http://localhost:{}/../../../../etc/passwd", port).as_str())?
+            .status();
+    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+
+    child.kill()?;
+
+    Ok(())
+}
+
+#[rstest]
+fn subdirectory_listing_links_stay_under_the_subdirectory(
+    tmpdir: TempDir,
+    port: u16,
+) -> Result<(), Error> {
+    let subdir = tmpdir.child("subdir");
+    subdir.create_dir_all()?;
+    subdir.child("nested.txt").write_str("nested contents")?;
+
+    let mut child = Command::cargo_bin("miniserve")?
+        .arg(tmpdir.path())
+        .arg("-p")
+        .arg(port.to_string())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    sleep(Duration::from_secs(1));
+
+    let body =
+        reqwest::get(format!("http://localhost:{}/subdir", port).as_str())?.error_for_status()?;
+    let parsed = Document::from_read(body)?;
+    let href = parsed
+        .find(Text)
+        .find(|text| text.text() == "nested.txt")
+        .and_then(|text| text.parent())
+        .and_then(|link| link.attr("href"))
+        .expect("Couldn't find a link to nested.txt")
+        .to_owned();
+
+    assert_eq!(href, "/subdir/nested.txt");
+
+    let mut body =
+        reqwest::get(format!("http://localhost:{}{}", port, href).as_str())?.error_for_status()?;
+    assert_eq!(body.text()?, "nested contents");
+
+    child.kill()?;
+
+    Ok(())
+}
+
 #[test]
 /// Show help and exit.
 fn help_shows() -> Result<(), Error> {